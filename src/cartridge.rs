@@ -0,0 +1,352 @@
+/// Represents an address to a location in the Gameboy's memory.
+type Address = u16;
+
+/// The location, in the ROM header, of the cartridge type byte. This byte
+/// tells us which memory bank controller (if any) the cartridge uses.
+const HEADER_CART_TYPE: usize = 0x0147;
+
+/// The location, in the ROM header, of the ROM size code.
+const HEADER_ROM_SIZE: usize = 0x0148;
+
+/// The location, in the ROM header, of the external RAM size code.
+const HEADER_RAM_SIZE: usize = 0x0149;
+
+/// The size, in bytes, of a single switchable ROM bank.
+const ROM_BANK_SIZE: usize = 0x4000;
+
+/// The size, in bytes, of a single switchable external RAM bank.
+const RAM_BANK_SIZE: usize = 0x2000;
+
+/// The banking mode selected by a write to `0x6000..=0x7FFF` on an MBC1
+/// cartridge. In simple mode, the upper two bank bits only ever affect the
+/// ROM bank used for `0x4000..=0x7FFF`. In advanced mode, those same bits
+/// also select which bank is visible at `0x0000..=0x3FFF` and which RAM
+/// bank is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Mbc1Mode {
+    Simple,
+    Advanced,
+}
+
+/// The memory bank controller a cartridge was built with, together with
+/// whatever state it needs to track bank switching and RAM enable.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Mbc {
+    /// No bank controller. ROM bank 1 is permanently mapped at
+    /// `0x4000..=0x7FFF`, and RAM (if present) is a single fixed bank.
+    None,
+
+    /// MBC1: 5-bit ROM bank register, 2-bit bank2/RAM-bank register, and a
+    /// simple/advanced banking mode latch.
+    Mbc1 {
+        rom_bank: u8,
+        bank2: u8,
+        mode: Mbc1Mode,
+        ram_enabled: bool,
+    },
+
+    /// MBC3: linear 7-bit ROM bank register, plus RAM/RTC bank select.
+    /// The RTC registers themselves are not emulated; RAM bank indices
+    /// `0x08..=0x0C` are recognised but read back as `0xFF`.
+    Mbc3 {
+        rom_bank: u8,
+        ram_bank: u8,
+        ram_enabled: bool,
+    },
+
+    /// MBC5: 9-bit ROM bank split across two registers, and a 4-bit RAM
+    /// bank register.
+    Mbc5 {
+        rom_bank: u16,
+        ram_bank: u8,
+        ram_enabled: bool,
+    },
+}
+
+impl Mbc {
+    /// Picks the bank controller implied by the cartridge type byte at
+    /// `0x0147` in the ROM header.
+    fn for_cart_type(cart_type: u8) -> Mbc {
+        match cart_type {
+            0x01..=0x03 => Mbc::Mbc1 {
+                rom_bank: 1,
+                bank2: 0,
+                mode: Mbc1Mode::Simple,
+                ram_enabled: false,
+            },
+            0x0F..=0x13 => Mbc::Mbc3 {
+                rom_bank: 1,
+                ram_bank: 0,
+                ram_enabled: false,
+            },
+            0x19..=0x1E => Mbc::Mbc5 {
+                rom_bank: 1,
+                ram_bank: 0,
+                ram_enabled: false,
+            },
+            _ => Mbc::None,
+        }
+    }
+}
+
+/// Converts the ROM size code at `0x0148` into a bank count. Every code
+/// doubles the 32 KiB (2 bank) base size. Real cartridges only use
+/// `0x00..=0x08`; a malformed or truncated image could have anything in
+/// that header byte, so it's clamped to the largest real code rather than
+/// overflowing the shift.
+fn rom_bank_count(code: u8) -> usize {
+    let code = code.min(0x08);
+    2usize.saturating_mul(1 << (code as u32))
+}
+
+/// Converts the external RAM size code at `0x0149` into a byte count.
+fn ram_size_bytes(code: u8) -> usize {
+    match code {
+        0x01 => 2 * 1024,
+        0x02 => 8 * 1024,
+        0x03 => 32 * 1024,
+        0x04 => 128 * 1024,
+        0x05 => 64 * 1024,
+        _ => 0,
+    }
+}
+
+/// Owns a cartridge's full ROM image and external RAM, and implements
+/// whichever memory bank controller its header declares.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cartridge {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    mbc: Mbc,
+}
+
+impl Cartridge {
+    /// Builds a `Cartridge` from a raw ROM image, parsing the header to
+    /// determine the bank controller and RAM size.
+    pub fn new(rom: Vec<u8>) -> Cartridge {
+        let cart_type = *rom.get(HEADER_CART_TYPE).unwrap_or(&0);
+        let ram_size = ram_size_bytes(*rom.get(HEADER_RAM_SIZE).unwrap_or(&0));
+        let mbc = Mbc::for_cart_type(cart_type);
+
+        Cartridge {
+            rom,
+            ram: vec![0u8; ram_size],
+            mbc,
+        }
+    }
+
+    /// The number of 16 KiB ROM banks on this cartridge, derived from the
+    /// header's ROM size code.
+    fn bank_count(&self) -> usize {
+        let count = rom_bank_count(*self.rom.get(HEADER_ROM_SIZE).unwrap_or(&0));
+        if count == 0 { 1 } else { count }
+    }
+
+    /// Reads a byte from `0x0000..=0x7FFF`.
+    pub fn read_rom(&self, addr: Address) -> u8 {
+        let offset = addr as usize;
+
+        let bank = match self.mbc {
+            Mbc::None => if offset < ROM_BANK_SIZE { 0 } else { 1 },
+            Mbc::Mbc1 { rom_bank, bank2, mode, .. } => {
+                if offset < ROM_BANK_SIZE {
+                    if mode == Mbc1Mode::Advanced { (bank2 << 5) as usize } else { 0 }
+                } else {
+                    ((bank2 << 5) | rom_bank) as usize
+                }
+            }
+            Mbc::Mbc3 { rom_bank, .. } => {
+                if offset < ROM_BANK_SIZE { 0 } else { rom_bank as usize }
+            }
+            Mbc::Mbc5 { rom_bank, .. } => {
+                if offset < ROM_BANK_SIZE { 0 } else { rom_bank as usize }
+            }
+        };
+
+        let bank = bank % self.bank_count();
+        let index = bank * ROM_BANK_SIZE + (offset % ROM_BANK_SIZE);
+        *self.rom.get(index).unwrap_or(&0xFF)
+    }
+
+    /// Handles a write into `0x0000..=0x7FFF`, which on a real cartridge
+    /// never touches ROM contents but instead drives the bank controller's
+    /// registers.
+    pub fn write_rom(&mut self, addr: Address, data: u8) {
+        match self.mbc {
+            Mbc::None => {}
+            Mbc::Mbc1 { ref mut rom_bank, ref mut bank2, ref mut mode, ref mut ram_enabled } => {
+                match addr {
+                    0x0000..=0x1FFF => *ram_enabled = (data & 0x0F) == 0x0A,
+                    0x2000..=0x3FFF => {
+                        let low5 = data & 0x1F;
+                        *rom_bank = if low5 == 0 { 1 } else { low5 };
+                    }
+                    0x4000..=0x5FFF => *bank2 = data & 0x03,
+                    0x6000..=0x7FFF => {
+                        *mode = if data & 0x01 == 0 { Mbc1Mode::Simple } else { Mbc1Mode::Advanced };
+                    }
+                    _ => {}
+                }
+            }
+            Mbc::Mbc3 { ref mut rom_bank, ref mut ram_bank, ref mut ram_enabled } => {
+                match addr {
+                    0x0000..=0x1FFF => *ram_enabled = (data & 0x0F) == 0x0A,
+                    0x2000..=0x3FFF => {
+                        let low7 = data & 0x7F;
+                        *rom_bank = if low7 == 0 { 1 } else { low7 };
+                    }
+                    0x4000..=0x5FFF => *ram_bank = data,
+                    _ => {}
+                }
+            }
+            Mbc::Mbc5 { ref mut rom_bank, ref mut ram_bank, ref mut ram_enabled } => {
+                match addr {
+                    0x0000..=0x1FFF => *ram_enabled = (data & 0x0F) == 0x0A,
+                    0x2000..=0x2FFF => *rom_bank = (*rom_bank & 0x100) | data as u16,
+                    0x3000..=0x3FFF => *rom_bank = (*rom_bank & 0x0FF) | (((data & 0x01) as u16) << 8),
+                    0x4000..=0x5FFF => *ram_bank = data & 0x0F,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Returns whether external RAM is currently enabled.
+    fn ram_enabled(&self) -> bool {
+        match self.mbc {
+            Mbc::None => true,
+            Mbc::Mbc1 { ram_enabled, .. } => ram_enabled,
+            Mbc::Mbc3 { ram_enabled, .. } => ram_enabled,
+            Mbc::Mbc5 { ram_enabled, .. } => ram_enabled,
+        }
+    }
+
+    /// The currently-selected RAM bank, or `None` for an MBC3 RTC register
+    /// selection, which this cartridge model doesn't back with storage.
+    fn ram_bank(&self) -> Option<usize> {
+        match self.mbc {
+            Mbc::None => Some(0),
+            Mbc::Mbc1 { bank2, mode, .. } => {
+                Some(if mode == Mbc1Mode::Advanced { bank2 as usize } else { 0 })
+            }
+            Mbc::Mbc3 { ram_bank, .. } => {
+                if ram_bank <= 0x03 { Some(ram_bank as usize) } else { None }
+            }
+            Mbc::Mbc5 { ram_bank, .. } => Some(ram_bank as usize),
+        }
+    }
+
+    /// Reads a byte from `0xA000..=0xBFFF`.
+    pub fn read_ram(&self, addr: Address) -> u8 {
+        if self.ram.is_empty() || !self.ram_enabled() {
+            return 0xFF;
+        }
+
+        match self.ram_bank() {
+            Some(bank) => {
+                let index = bank * RAM_BANK_SIZE + (addr - EXRAM_START) as usize;
+                *self.ram.get(index).unwrap_or(&0xFF)
+            }
+            None => 0xFF,
+        }
+    }
+
+    /// Writes a byte to `0xA000..=0xBFFF`.
+    pub fn write_ram(&mut self, addr: Address, data: u8) {
+        if self.ram.is_empty() || !self.ram_enabled() {
+            return;
+        }
+
+        if let Some(bank) = self.ram_bank() {
+            let index = bank * RAM_BANK_SIZE + (addr - EXRAM_START) as usize;
+            if let Some(slot) = self.ram.get_mut(index) {
+                *slot = data;
+            }
+        }
+    }
+
+    /// Whether this cartridge has any battery-backed RAM worth persisting.
+    pub fn has_battery_ram(&self) -> bool {
+        let has_battery = match *self.rom.get(HEADER_CART_TYPE).unwrap_or(&0) {
+            0x03 | 0x06 | 0x09 | 0x0D | 0x0F | 0x10 | 0x13 | 0x1B | 0x1E | 0xFF => true,
+            _ => false,
+        };
+        has_battery && !self.ram.is_empty()
+    }
+
+    /// Returns the current contents of battery-backed RAM, to be written
+    /// out to a save file.
+    pub fn save_ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Restores battery-backed RAM from a previously saved buffer. The
+    /// buffer is copied in up to the smaller of the two lengths, so a save
+    /// from a differently-sized cartridge doesn't panic.
+    pub fn load_ram(&mut self, data: &[u8]) {
+        let len = self.ram.len().min(data.len());
+        self.ram[..len].copy_from_slice(&data[..len]);
+    }
+}
+
+// Mirrors the constant of the same name in `memory`; external RAM always
+// starts at this address regardless of which bank is mapped in.
+const EXRAM_START: Address = 0xA000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds a synthetic ROM with the given header bytes, sized to its
+    // declared bank count, with each bank's first byte set to its own
+    // index so `read_rom` can report which bank actually got mapped in.
+    fn test_rom(cart_type: u8, rom_size_code: u8, ram_size_code: u8) -> Vec<u8> {
+        let banks = rom_bank_count(rom_size_code).max(1);
+        let mut rom = vec![0u8; banks * ROM_BANK_SIZE];
+        for (bank, chunk) in rom.chunks_mut(ROM_BANK_SIZE).enumerate() {
+            chunk[0] = bank as u8;
+        }
+        rom[HEADER_CART_TYPE] = cart_type;
+        rom[HEADER_ROM_SIZE] = rom_size_code;
+        rom[HEADER_RAM_SIZE] = ram_size_code;
+        rom
+    }
+
+    #[test]
+    fn mbc1_bank_register_wraps_zero_to_one() {
+        let mut cart = Cartridge::new(test_rom(0x01, 0x05, 0x00));
+
+        for low5 in &[0x00u8, 0x20, 0x40, 0x60] {
+            cart.write_rom(0x2000, *low5);
+            assert_eq!(cart.read_rom(0x4000), 1);
+        }
+    }
+
+    #[test]
+    fn mbc1_bank2_feeds_both_rom_windows_in_advanced_mode() {
+        let mut cart = Cartridge::new(test_rom(0x01, 0x05, 0x00));
+
+        cart.write_rom(0x2000, 0x01); // rom_bank = 1
+        cart.write_rom(0x4000, 0x01); // bank2 = 1
+        cart.write_rom(0x6000, 0x01); // advanced mode
+
+        assert_eq!(cart.read_rom(0x0000), 0b0010_0000); // bank2 << 5, rom_bank ignored
+        assert_eq!(cart.read_rom(0x4000), 0b0010_0001); // (bank2 << 5) | rom_bank
+    }
+
+    #[test]
+    fn mbc1_ram_reads_and_writes_are_gated_by_the_enable_latch() {
+        let mut cart = Cartridge::new(test_rom(0x01, 0x00, 0x02));
+
+        assert_eq!(cart.read_ram(0xA000), 0xFF);
+
+        cart.write_rom(0x0000, 0x0A);
+        cart.write_ram(0xA000, 0x42);
+        assert_eq!(cart.read_ram(0xA000), 0x42);
+
+        cart.write_rom(0x0000, 0x00);
+        assert_eq!(cart.read_ram(0xA000), 0xFF);
+    }
+}
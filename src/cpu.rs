@@ -0,0 +1,1044 @@
+use register::{Register, RegOps, RegDataArray, StatusFlags, Condition, Z_FLAG, N_FLAG, H_FLAG, C_FLAG};
+use memory::Memory;
+use interrupt::InterruptSource;
+
+/// A single entry in an opcode dispatch table: the instruction's mnemonic,
+/// its encoded length in bytes (opcode byte plus operands), and its cycle
+/// cost when it does not take a branch. Conditional instructions (`JR cc`,
+/// `CALL cc`, `RET cc`) cost more when the branch is taken; `step` resolves
+/// the actual cycle count for those at execution time rather than from
+/// this table.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+struct Opcode {
+    mnemonic: &'static str,
+    length: u8,
+    cycles: u8,
+}
+
+/// The 256-entry table of unprefixed opcodes, indexed by opcode byte.
+const MAIN_TABLE: [Opcode; 256] = [
+    Opcode { mnemonic: "NOP", length: 1, cycles: 4 }, Opcode { mnemonic: "LD BC,d16", length: 3, cycles: 12 }, Opcode { mnemonic: "LD (BC),A", length: 1, cycles: 8 }, Opcode { mnemonic: "INC BC", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC B", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RLCA", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD (a16),SP", length: 3, cycles: 20 }, Opcode { mnemonic: "ADD HL,BC", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,(BC)", length: 1, cycles: 8 }, Opcode { mnemonic: "DEC BC", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC C", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RRCA", length: 1, cycles: 4 },
+    Opcode { mnemonic: "STOP", length: 2, cycles: 4 }, Opcode { mnemonic: "LD DE,d16", length: 3, cycles: 12 }, Opcode { mnemonic: "LD (DE),A", length: 1, cycles: 8 }, Opcode { mnemonic: "INC DE", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC D", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RLA", length: 1, cycles: 4 },
+    Opcode { mnemonic: "JR r8", length: 2, cycles: 12 }, Opcode { mnemonic: "ADD HL,DE", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,(DE)", length: 1, cycles: 8 }, Opcode { mnemonic: "DEC DE", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC E", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC E", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RRA", length: 1, cycles: 4 },
+    Opcode { mnemonic: "JR NZ,r8", length: 2, cycles: 8 }, Opcode { mnemonic: "LD HL,d16", length: 3, cycles: 12 }, Opcode { mnemonic: "LD (HL+),A", length: 1, cycles: 8 }, Opcode { mnemonic: "INC HL", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC H", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "DAA", length: 1, cycles: 4 },
+    Opcode { mnemonic: "JR Z,r8", length: 2, cycles: 8 }, Opcode { mnemonic: "ADD HL,HL", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,(HL+)", length: 1, cycles: 8 }, Opcode { mnemonic: "DEC HL", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC L", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "CPL", length: 1, cycles: 4 },
+    Opcode { mnemonic: "JR NC,r8", length: 2, cycles: 8 }, Opcode { mnemonic: "LD SP,d16", length: 3, cycles: 12 }, Opcode { mnemonic: "LD (HL-),A", length: 1, cycles: 8 }, Opcode { mnemonic: "INC SP", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC (HL)", length: 1, cycles: 12 }, Opcode { mnemonic: "DEC (HL)", length: 1, cycles: 12 }, Opcode { mnemonic: "LD (HL),d8", length: 2, cycles: 12 }, Opcode { mnemonic: "SCF", length: 1, cycles: 4 },
+    Opcode { mnemonic: "JR C,r8", length: 2, cycles: 8 }, Opcode { mnemonic: "ADD HL,SP", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,(HL-)", length: 1, cycles: 8 }, Opcode { mnemonic: "DEC SP", length: 1, cycles: 8 },
+    Opcode { mnemonic: "INC A", length: 1, cycles: 4 }, Opcode { mnemonic: "DEC A", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "CCF", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD B,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD B,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD B,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD B,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD C,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD C,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD C,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD C,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD D,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD D,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD D,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD D,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD E,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD E,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD E,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD E,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD H,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD H,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD H,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD H,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD L,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD L,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD L,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD L,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD (HL),B", length: 1, cycles: 8 }, Opcode { mnemonic: "LD (HL),C", length: 1, cycles: 8 }, Opcode { mnemonic: "LD (HL),D", length: 1, cycles: 8 }, Opcode { mnemonic: "LD (HL),E", length: 1, cycles: 8 },
+    Opcode { mnemonic: "LD (HL),H", length: 1, cycles: 8 }, Opcode { mnemonic: "LD (HL),L", length: 1, cycles: 8 }, Opcode { mnemonic: "HALT", length: 1, cycles: 4 }, Opcode { mnemonic: "LD (HL),A", length: 1, cycles: 8 },
+    Opcode { mnemonic: "LD A,B", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,C", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,D", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "LD A,H", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,L", length: 1, cycles: 4 }, Opcode { mnemonic: "LD A,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "ADD A,B", length: 1, cycles: 4 }, Opcode { mnemonic: "ADD A,C", length: 1, cycles: 4 }, Opcode { mnemonic: "ADD A,D", length: 1, cycles: 4 }, Opcode { mnemonic: "ADD A,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "ADD A,H", length: 1, cycles: 4 }, Opcode { mnemonic: "ADD A,L", length: 1, cycles: 4 }, Opcode { mnemonic: "ADD A,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "ADD A,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "ADC A,B", length: 1, cycles: 4 }, Opcode { mnemonic: "ADC A,C", length: 1, cycles: 4 }, Opcode { mnemonic: "ADC A,D", length: 1, cycles: 4 }, Opcode { mnemonic: "ADC A,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "ADC A,H", length: 1, cycles: 4 }, Opcode { mnemonic: "ADC A,L", length: 1, cycles: 4 }, Opcode { mnemonic: "ADC A,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "ADC A,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "SUB,B", length: 1, cycles: 4 }, Opcode { mnemonic: "SUB,C", length: 1, cycles: 4 }, Opcode { mnemonic: "SUB,D", length: 1, cycles: 4 }, Opcode { mnemonic: "SUB,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "SUB,H", length: 1, cycles: 4 }, Opcode { mnemonic: "SUB,L", length: 1, cycles: 4 }, Opcode { mnemonic: "SUB,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "SUB,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "SBC A,B", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,C", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,D", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "SBC A,H", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,L", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "SBC A,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "AND,B", length: 1, cycles: 4 }, Opcode { mnemonic: "AND,C", length: 1, cycles: 4 }, Opcode { mnemonic: "AND,D", length: 1, cycles: 4 }, Opcode { mnemonic: "AND,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "AND,H", length: 1, cycles: 4 }, Opcode { mnemonic: "AND,L", length: 1, cycles: 4 }, Opcode { mnemonic: "AND,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "AND,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "XOR,B", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR,C", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR,D", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "XOR,H", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR,L", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "XOR,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "OR,B", length: 1, cycles: 4 }, Opcode { mnemonic: "OR,C", length: 1, cycles: 4 }, Opcode { mnemonic: "OR,D", length: 1, cycles: 4 }, Opcode { mnemonic: "OR,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "OR,H", length: 1, cycles: 4 }, Opcode { mnemonic: "OR,L", length: 1, cycles: 4 }, Opcode { mnemonic: "OR,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "OR,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "CP,B", length: 1, cycles: 4 }, Opcode { mnemonic: "CP,C", length: 1, cycles: 4 }, Opcode { mnemonic: "CP,D", length: 1, cycles: 4 }, Opcode { mnemonic: "CP,E", length: 1, cycles: 4 },
+    Opcode { mnemonic: "CP,H", length: 1, cycles: 4 }, Opcode { mnemonic: "CP,L", length: 1, cycles: 4 }, Opcode { mnemonic: "CP,(HL)", length: 1, cycles: 8 }, Opcode { mnemonic: "CP,A", length: 1, cycles: 4 },
+    Opcode { mnemonic: "RET NZ", length: 1, cycles: 8 }, Opcode { mnemonic: "POP BC", length: 1, cycles: 12 }, Opcode { mnemonic: "JP NZ,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "JP a16", length: 3, cycles: 16 },
+    Opcode { mnemonic: "CALL NZ,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "PUSH BC", length: 1, cycles: 16 }, Opcode { mnemonic: "ADD A,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 00H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "RET Z", length: 1, cycles: 8 }, Opcode { mnemonic: "RET", length: 1, cycles: 16 }, Opcode { mnemonic: "JP Z,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "PREFIX CB", length: 1, cycles: 4 },
+    Opcode { mnemonic: "CALL Z,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "CALL a16", length: 3, cycles: 24 }, Opcode { mnemonic: "ADC A,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 08H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "RET NC", length: 1, cycles: 8 }, Opcode { mnemonic: "POP DE", length: 1, cycles: 12 }, Opcode { mnemonic: "JP NC,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 },
+    Opcode { mnemonic: "CALL NC,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "PUSH DE", length: 1, cycles: 16 }, Opcode { mnemonic: "SUB d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 10H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "RET C", length: 1, cycles: 8 }, Opcode { mnemonic: "RETI", length: 1, cycles: 16 }, Opcode { mnemonic: "JP C,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 },
+    Opcode { mnemonic: "CALL C,a16", length: 3, cycles: 12 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "SBC A,d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 18H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "LDH (a8),A", length: 2, cycles: 12 }, Opcode { mnemonic: "POP HL", length: 1, cycles: 12 }, Opcode { mnemonic: "LD (C),A", length: 1, cycles: 8 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 },
+    Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "PUSH HL", length: 1, cycles: 16 }, Opcode { mnemonic: "AND d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 20H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "ADD SP,r8", length: 2, cycles: 16 }, Opcode { mnemonic: "JP (HL)", length: 1, cycles: 4 }, Opcode { mnemonic: "LD (a16),A", length: 3, cycles: 16 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 },
+    Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "XOR d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 28H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "LDH A,(a8)", length: 2, cycles: 12 }, Opcode { mnemonic: "POP AF", length: 1, cycles: 12 }, Opcode { mnemonic: "LD A,(C)", length: 1, cycles: 8 }, Opcode { mnemonic: "DI", length: 1, cycles: 4 },
+    Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "PUSH AF", length: 1, cycles: 16 }, Opcode { mnemonic: "OR d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 30H", length: 1, cycles: 16 },
+    Opcode { mnemonic: "LD HL,SP+r8", length: 2, cycles: 12 }, Opcode { mnemonic: "LD SP,HL", length: 1, cycles: 8 }, Opcode { mnemonic: "LD A,(a16)", length: 3, cycles: 16 }, Opcode { mnemonic: "EI", length: 1, cycles: 4 },
+    Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "UNKNOWN", length: 1, cycles: 4 }, Opcode { mnemonic: "CP d8", length: 2, cycles: 8 }, Opcode { mnemonic: "RST 38H", length: 1, cycles: 16 },
+];
+
+/// The 256-entry table of `0xCB`-prefixed opcodes, indexed by the byte
+/// that follows the `0xCB` prefix.
+const CB_TABLE: [Opcode; 256] = [
+    Opcode { mnemonic: "RLC B", length: 2, cycles: 8 }, Opcode { mnemonic: "RLC C", length: 2, cycles: 8 }, Opcode { mnemonic: "RLC D", length: 2, cycles: 8 }, Opcode { mnemonic: "RLC E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RLC H", length: 2, cycles: 8 }, Opcode { mnemonic: "RLC L", length: 2, cycles: 8 }, Opcode { mnemonic: "RLC (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RLC A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RRC B", length: 2, cycles: 8 }, Opcode { mnemonic: "RRC C", length: 2, cycles: 8 }, Opcode { mnemonic: "RRC D", length: 2, cycles: 8 }, Opcode { mnemonic: "RRC E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RRC H", length: 2, cycles: 8 }, Opcode { mnemonic: "RRC L", length: 2, cycles: 8 }, Opcode { mnemonic: "RRC (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RRC A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RL B", length: 2, cycles: 8 }, Opcode { mnemonic: "RL C", length: 2, cycles: 8 }, Opcode { mnemonic: "RL D", length: 2, cycles: 8 }, Opcode { mnemonic: "RL E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RL H", length: 2, cycles: 8 }, Opcode { mnemonic: "RL L", length: 2, cycles: 8 }, Opcode { mnemonic: "RL (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RL A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RR B", length: 2, cycles: 8 }, Opcode { mnemonic: "RR C", length: 2, cycles: 8 }, Opcode { mnemonic: "RR D", length: 2, cycles: 8 }, Opcode { mnemonic: "RR E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RR H", length: 2, cycles: 8 }, Opcode { mnemonic: "RR L", length: 2, cycles: 8 }, Opcode { mnemonic: "RR (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RR A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SLA B", length: 2, cycles: 8 }, Opcode { mnemonic: "SLA C", length: 2, cycles: 8 }, Opcode { mnemonic: "SLA D", length: 2, cycles: 8 }, Opcode { mnemonic: "SLA E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SLA H", length: 2, cycles: 8 }, Opcode { mnemonic: "SLA L", length: 2, cycles: 8 }, Opcode { mnemonic: "SLA (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SLA A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SRA B", length: 2, cycles: 8 }, Opcode { mnemonic: "SRA C", length: 2, cycles: 8 }, Opcode { mnemonic: "SRA D", length: 2, cycles: 8 }, Opcode { mnemonic: "SRA E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SRA H", length: 2, cycles: 8 }, Opcode { mnemonic: "SRA L", length: 2, cycles: 8 }, Opcode { mnemonic: "SRA (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SRA A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SWAP B", length: 2, cycles: 8 }, Opcode { mnemonic: "SWAP C", length: 2, cycles: 8 }, Opcode { mnemonic: "SWAP D", length: 2, cycles: 8 }, Opcode { mnemonic: "SWAP E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SWAP H", length: 2, cycles: 8 }, Opcode { mnemonic: "SWAP L", length: 2, cycles: 8 }, Opcode { mnemonic: "SWAP (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SWAP A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SRL B", length: 2, cycles: 8 }, Opcode { mnemonic: "SRL C", length: 2, cycles: 8 }, Opcode { mnemonic: "SRL D", length: 2, cycles: 8 }, Opcode { mnemonic: "SRL E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SRL H", length: 2, cycles: 8 }, Opcode { mnemonic: "SRL L", length: 2, cycles: 8 }, Opcode { mnemonic: "SRL (HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SRL A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 0,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 0,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 0,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 0,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 0,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 0,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 0,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 0,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 1,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 1,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 1,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 1,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 1,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 1,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 1,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 1,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 2,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 2,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 2,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 2,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 2,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 2,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 2,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 2,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 3,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 3,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 3,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 3,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 3,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 3,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 3,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 3,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 4,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 4,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 4,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 4,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 4,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 4,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 4,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 4,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 5,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 5,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 5,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 5,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 5,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 5,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 5,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 5,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 6,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 6,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 6,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 6,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 6,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 6,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 6,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 6,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 7,B", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 7,C", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 7,D", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 7,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "BIT 7,H", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 7,L", length: 2, cycles: 8 }, Opcode { mnemonic: "BIT 7,(HL)", length: 2, cycles: 12 }, Opcode { mnemonic: "BIT 7,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 0,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 0,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 0,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 0,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 0,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 0,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 0,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 0,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 1,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 1,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 1,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 1,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 1,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 1,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 1,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 1,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 2,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 2,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 2,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 2,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 2,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 2,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 2,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 2,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 3,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 3,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 3,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 3,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 3,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 3,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 3,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 3,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 4,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 4,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 4,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 4,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 4,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 4,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 4,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 4,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 5,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 5,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 5,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 5,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 5,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 5,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 5,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 5,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 6,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 6,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 6,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 6,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 6,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 6,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 6,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 6,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 7,B", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 7,C", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 7,D", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 7,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "RES 7,H", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 7,L", length: 2, cycles: 8 }, Opcode { mnemonic: "RES 7,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "RES 7,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 0,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 0,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 0,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 0,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 0,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 0,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 0,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 0,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 1,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 1,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 1,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 1,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 1,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 1,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 1,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 1,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 2,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 2,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 2,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 2,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 2,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 2,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 2,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 2,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 3,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 3,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 3,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 3,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 3,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 3,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 3,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 3,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 4,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 4,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 4,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 4,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 4,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 4,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 4,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 4,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 5,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 5,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 5,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 5,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 5,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 5,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 5,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 5,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 6,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 6,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 6,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 6,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 6,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 6,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 6,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 6,A", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 7,B", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 7,C", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 7,D", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 7,E", length: 2, cycles: 8 },
+    Opcode { mnemonic: "SET 7,H", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 7,L", length: 2, cycles: 8 }, Opcode { mnemonic: "SET 7,(HL)", length: 2, cycles: 16 }, Opcode { mnemonic: "SET 7,A", length: 2, cycles: 8 },
+];
+
+/// Maps a 3-bit register-select field (as used in the `0x40..=0xBF` block
+/// of the main table) to a register, or `None` for the `(HL)` memory
+/// operand.
+const REG_TABLE: [Option<Register>; 8] = [
+    Some(Register::B), Some(Register::C), Some(Register::D), Some(Register::E),
+    Some(Register::H), Some(Register::L), None, Some(Register::A),
+];
+
+/// Drives a `Memory` by fetching, decoding, and executing instructions
+/// against a `RegDataArray`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cpu {
+    regs: RegDataArray,
+    /// The interrupt master enable flag. Interrupts are only dispatched by
+    /// `service_interrupts` while this is set.
+    ime: bool,
+}
+
+impl Cpu {
+    /// Builds a `Cpu` around an existing register file, with interrupts
+    /// disabled until an `EI` executes (matching the hardware reset state).
+    pub fn new(regs: RegDataArray) -> Cpu {
+        Cpu { regs, ime: false }
+    }
+
+    /// When IME is set, checks the five interrupt sources in priority
+    /// order and, for the highest-priority source enabled in both IE and
+    /// IF, dispatches to its handler: clears the IF bit, clears IME,
+    /// pushes `PC`, and jumps to the vector. Returns the number of clock
+    /// cycles the dispatch consumed (20, i.e. five machine cycles), or 0 if
+    /// no interrupt was serviced.
+    pub fn service_interrupts(&mut self, mem: &mut Memory) -> u8 {
+        if !self.ime {
+            return 0;
+        }
+
+        let enabled = mem.interrupt_enable();
+        let requested = mem.interrupt_flags();
+
+        for &source in InterruptSource::ALL.iter() {
+            let bit = 1 << source.bit();
+            if enabled & bit != 0 && requested & bit != 0 {
+                mem.clear_interrupt(source);
+                self.ime = false;
+                self.push(mem, Register::PC);
+                self.regs.write_dword(Register::PC, source.vector());
+                return 20;
+            }
+        }
+
+        0
+    }
+
+    /// The CPU's register file.
+    pub fn regs(&self) -> &RegDataArray {
+        &self.regs
+    }
+
+    /// The CPU's register file, mutably.
+    pub fn regs_mut(&mut self) -> &mut RegDataArray {
+        &mut self.regs
+    }
+
+    /// Fetches the opcode at `PC`, decodes and executes it, advances `PC`
+    /// past the instruction, and returns the number of clock cycles it
+    /// consumed (four per machine cycle, so `NOP` reports 4).
+    pub fn step(&mut self, mem: &mut Memory) -> u8 {
+        let opcode = self.fetch_byte(mem);
+
+        if opcode == 0xCB {
+            let cb_opcode = self.fetch_byte(mem);
+            self.execute_cb(cb_opcode, mem)
+        } else {
+            self.execute(opcode, mem)
+        }
+    }
+
+    /// Reads the byte at `PC` and advances `PC` past it.
+    fn fetch_byte(&mut self, mem: &mut Memory) -> u8 {
+        let pc = self.regs.read_dword(Register::PC);
+        let byte = mem.read_word(pc);
+        self.regs.write_dword(Register::PC, pc.wrapping_add(1));
+        byte
+    }
+
+    /// Reads the little-endian word at `PC` and advances `PC` past it.
+    fn fetch_word(&mut self, mem: &mut Memory) -> u16 {
+        let low = self.fetch_byte(mem) as u16;
+        let high = self.fetch_byte(mem) as u16;
+        (high << 8) | low
+    }
+
+    /// Reads the 8-bit operand named by a 3-bit register-select field,
+    /// treating `0b110` as `(HL)`.
+    fn read_r8(&self, bits: u8, mem: &Memory) -> u8 {
+        match REG_TABLE[bits as usize] {
+            Some(reg) => self.regs.read_word(reg),
+            None => mem.read_word(self.regs.read_dword(Register::HL)),
+        }
+    }
+
+    /// Writes the 8-bit operand named by a 3-bit register-select field,
+    /// treating `0b110` as `(HL)`.
+    fn write_r8(&mut self, bits: u8, mem: &mut Memory, value: u8) {
+        match REG_TABLE[bits as usize] {
+            Some(reg) => self.regs.write_word(reg, value),
+            None => mem.write_word(self.regs.read_dword(Register::HL), value),
+        }
+    }
+
+    /// Maps the two bits selecting a 16-bit register in the `0x01`/`0x03`/
+    /// `0x0B`-style opcode groups to BC/DE/HL/SP.
+    fn dword_reg(opcode: u8) -> Register {
+        match (opcode >> 4) & 0x03 {
+            0 => Register::BC,
+            1 => Register::DE,
+            2 => Register::HL,
+            _ => Register::SP,
+        }
+    }
+
+    // ---- 8-bit loads, arithmetic, and INC/DEC -----------------------------
+
+    /// Applies one of the eight ALU operations selected by the `opcode >> 3
+    /// & 0x07` field (ADD, ADC, SUB, SBC, AND, XOR, OR, CP, in that order)
+    /// to the accumulator.
+    fn alu_op(&mut self, op: u8, value: u8) {
+        match op {
+            0 => self.add_a(value, false),
+            1 => self.add_a(value, true),
+            2 => { let r = self.sub_a(value, false); self.regs.write_word(Register::A, r); }
+            3 => { let r = self.sub_a(value, true); self.regs.write_word(Register::A, r); }
+            4 => self.and_a(value),
+            5 => self.xor_a(value),
+            6 => self.or_a(value),
+            7 => { self.sub_a(value, false); }
+            _ => unreachable!(),
+        }
+    }
+
+    fn add_a(&mut self, value: u8, with_carry: bool) {
+        let a = self.regs.read_word(Register::A);
+        let carry_in = if with_carry && self.regs.get_flags().contains(C_FLAG) { 1u16 } else { 0u16 };
+        let result = a as u16 + value as u16 + carry_in;
+
+        let mut flags = StatusFlags::empty();
+        if (result & 0xFF) == 0 { flags.insert(Z_FLAG); }
+        if (a & 0x0F) + (value & 0x0F) + carry_in as u8 > 0x0F { flags.insert(H_FLAG); }
+        if result > 0xFF { flags.insert(C_FLAG); }
+
+        self.regs.set_flags(flags);
+        self.regs.write_word(Register::A, result as u8);
+    }
+
+    /// Computes `A - value [- carry]`, setting flags as for SUB/SBC/CP, and
+    /// returns the result without writing it back (CP discards it; SUB/SBC
+    /// write it back to `A` themselves).
+    fn sub_a(&mut self, value: u8, with_carry: bool) -> u8 {
+        let a = self.regs.read_word(Register::A);
+        let carry_in = if with_carry && self.regs.get_flags().contains(C_FLAG) { 1i16 } else { 0i16 };
+        let result = a as i16 - value as i16 - carry_in;
+
+        let mut flags = N_FLAG;
+        if (result as u8) == 0 { flags.insert(Z_FLAG); }
+        if (a as i16 & 0x0F) - (value as i16 & 0x0F) - carry_in < 0 { flags.insert(H_FLAG); }
+        if result < 0 { flags.insert(C_FLAG); }
+
+        self.regs.set_flags(flags);
+        result as u8
+    }
+
+    fn and_a(&mut self, value: u8) {
+        let result = self.regs.read_word(Register::A) & value;
+        let mut flags = H_FLAG;
+        if result == 0 { flags.insert(Z_FLAG); }
+        self.regs.set_flags(flags);
+        self.regs.write_word(Register::A, result);
+    }
+
+    fn or_a(&mut self, value: u8) {
+        let result = self.regs.read_word(Register::A) | value;
+        let mut flags = StatusFlags::empty();
+        if result == 0 { flags.insert(Z_FLAG); }
+        self.regs.set_flags(flags);
+        self.regs.write_word(Register::A, result);
+    }
+
+    fn xor_a(&mut self, value: u8) {
+        let result = self.regs.read_word(Register::A) ^ value;
+        let mut flags = StatusFlags::empty();
+        if result == 0 { flags.insert(Z_FLAG); }
+        self.regs.set_flags(flags);
+        self.regs.write_word(Register::A, result);
+    }
+
+    /// `INC r8`/`INC (HL)`: increments a value, updating Z/N/H but leaving
+    /// the carry flag untouched.
+    fn inc8(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_add(1);
+        let mut flags = self.regs.get_flags() & C_FLAG;
+        if result == 0 { flags.insert(Z_FLAG); }
+        if value & 0x0F == 0x0F { flags.insert(H_FLAG); }
+        self.regs.set_flags(flags);
+        result
+    }
+
+    /// `DEC r8`/`DEC (HL)`: decrements a value, updating Z/N/H but leaving
+    /// the carry flag untouched.
+    fn dec8(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_sub(1);
+        let mut flags = (self.regs.get_flags() & C_FLAG) | N_FLAG;
+        if result == 0 { flags.insert(Z_FLAG); }
+        if value & 0x0F == 0x00 { flags.insert(H_FLAG); }
+        self.regs.set_flags(flags);
+        result
+    }
+
+    /// `ADD HL,rr`: adds a 16-bit register into `HL`, updating N/H/C but
+    /// leaving the zero flag untouched.
+    fn add_hl(&mut self, reg: Register) {
+        let hl = self.regs.read_dword(Register::HL);
+        let value = self.regs.read_dword(reg);
+        let result = hl as u32 + value as u32;
+
+        let mut flags = self.regs.get_flags() & Z_FLAG;
+        if (hl & 0x0FFF) + (value & 0x0FFF) > 0x0FFF { flags.insert(H_FLAG); }
+        if result > 0xFFFF { flags.insert(C_FLAG); }
+
+        self.regs.set_flags(flags);
+        self.regs.write_dword(Register::HL, result as u16);
+    }
+
+    /// `ADD SP,r8` / `LD HL,SP+r8`: adds a signed 8-bit offset to `sp`,
+    /// setting Z/N clear and H/C from the *unsigned* low-byte addition (as
+    /// the hardware does for both of these opcodes), and returns the
+    /// 16-bit result without writing it back.
+    fn add_sp_offset(&mut self, sp: u16, offset: i8) -> u16 {
+        let value = offset as i16 as u16;
+        let result = sp.wrapping_add(value);
+
+        let mut flags = StatusFlags::empty();
+        if (sp & 0x0F) + (value & 0x0F) > 0x0F { flags.insert(H_FLAG); }
+        if (sp & 0xFF) + (value & 0xFF) > 0xFF { flags.insert(C_FLAG); }
+
+        self.regs.set_flags(flags);
+        result
+    }
+
+    /// `DAA`: adjusts `A` after a BCD addition or subtraction, using the
+    /// N/H/C flags left by that operation so the result is a valid
+    /// two-digit BCD value.
+    fn daa(&mut self) {
+        let mut a = self.regs.read_word(Register::A);
+        let flags = self.regs.get_flags();
+        let mut carry = flags.contains(C_FLAG);
+
+        if !flags.contains(N_FLAG) {
+            if carry || a > 0x99 { a = a.wrapping_add(0x60); carry = true; }
+            if flags.contains(H_FLAG) || (a & 0x0F) > 0x09 { a = a.wrapping_add(0x06); }
+        } else {
+            if carry { a = a.wrapping_sub(0x60); }
+            if flags.contains(H_FLAG) { a = a.wrapping_sub(0x06); }
+        }
+
+        let mut result = flags & N_FLAG;
+        if a == 0 { result.insert(Z_FLAG); }
+        if carry { result.insert(C_FLAG); }
+
+        self.regs.set_flags(result);
+        self.regs.write_word(Register::A, a);
+    }
+
+    /// `RLCA`: rotates `A` left by one bit into carry. Unlike `0xCB RLC r`,
+    /// this always clears the zero flag regardless of the result.
+    fn rlca(&mut self) {
+        let a = self.regs.read_word(Register::A);
+        let carry = a & 0x80 != 0;
+        self.regs.write_word(Register::A, a.rotate_left(1));
+        let mut flags = StatusFlags::empty();
+        if carry { flags.insert(C_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    /// `RRCA`: rotates `A` right by one bit into carry, always clearing Z.
+    fn rrca(&mut self) {
+        let a = self.regs.read_word(Register::A);
+        let carry = a & 0x01 != 0;
+        self.regs.write_word(Register::A, a.rotate_right(1));
+        let mut flags = StatusFlags::empty();
+        if carry { flags.insert(C_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    /// `RLA`: rotates `A` left through carry, always clearing Z.
+    fn rla(&mut self) {
+        let a = self.regs.read_word(Register::A);
+        let carry_in = self.regs.get_flags().contains(C_FLAG) as u8;
+        let carry_out = a & 0x80 != 0;
+        self.regs.write_word(Register::A, (a << 1) | carry_in);
+        let mut flags = StatusFlags::empty();
+        if carry_out { flags.insert(C_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    /// `RRA`: rotates `A` right through carry, always clearing Z.
+    fn rra(&mut self) {
+        let a = self.regs.read_word(Register::A);
+        let carry_in = self.regs.get_flags().contains(C_FLAG) as u8;
+        let carry_out = a & 0x01 != 0;
+        self.regs.write_word(Register::A, (a >> 1) | (carry_in << 7));
+        let mut flags = StatusFlags::empty();
+        if carry_out { flags.insert(C_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    /// Sets Z (from `result`) and C (from `carry`), clearing N/H, as every
+    /// `0xCB`-prefixed rotate/shift instruction does.
+    fn set_shift_flags(&mut self, result: u8, carry: bool) {
+        let mut flags = StatusFlags::empty();
+        if result == 0 { flags.insert(Z_FLAG); }
+        if carry { flags.insert(C_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    /// `RLC r`/`RLC (HL)`: rotates left by one bit into carry.
+    fn rlc(&mut self, value: u8) -> u8 {
+        let carry = value & 0x80 != 0;
+        let result = value.rotate_left(1);
+        self.set_shift_flags(result, carry);
+        result
+    }
+
+    /// `RRC r`/`RRC (HL)`: rotates right by one bit into carry.
+    fn rrc(&mut self, value: u8) -> u8 {
+        let carry = value & 0x01 != 0;
+        let result = value.rotate_right(1);
+        self.set_shift_flags(result, carry);
+        result
+    }
+
+    /// `RL r`/`RL (HL)`: rotates left through carry.
+    fn rl(&mut self, value: u8) -> u8 {
+        let carry_in = self.regs.get_flags().contains(C_FLAG) as u8;
+        let carry_out = value & 0x80 != 0;
+        let result = (value << 1) | carry_in;
+        self.set_shift_flags(result, carry_out);
+        result
+    }
+
+    /// `RR r`/`RR (HL)`: rotates right through carry.
+    fn rr(&mut self, value: u8) -> u8 {
+        let carry_in = self.regs.get_flags().contains(C_FLAG) as u8;
+        let carry_out = value & 0x01 != 0;
+        let result = (value >> 1) | (carry_in << 7);
+        self.set_shift_flags(result, carry_out);
+        result
+    }
+
+    /// `SLA r`/`SLA (HL)`: arithmetic shift left, shifting zero into bit 0.
+    fn sla(&mut self, value: u8) -> u8 {
+        let carry = value & 0x80 != 0;
+        let result = value << 1;
+        self.set_shift_flags(result, carry);
+        result
+    }
+
+    /// `SRA r`/`SRA (HL)`: arithmetic shift right, preserving bit 7.
+    fn sra(&mut self, value: u8) -> u8 {
+        let carry = value & 0x01 != 0;
+        let result = (value >> 1) | (value & 0x80);
+        self.set_shift_flags(result, carry);
+        result
+    }
+
+    /// `SWAP r`/`SWAP (HL)`: swaps the two nibbles, always clearing carry.
+    fn swap(&mut self, value: u8) -> u8 {
+        let result = value.rotate_right(4);
+        self.set_shift_flags(result, false);
+        result
+    }
+
+    /// `SRL r`/`SRL (HL)`: logical shift right, shifting zero into bit 7.
+    fn srl(&mut self, value: u8) -> u8 {
+        let carry = value & 0x01 != 0;
+        let result = value >> 1;
+        self.set_shift_flags(result, carry);
+        result
+    }
+
+    /// `BIT b,r`/`BIT b,(HL)`: sets Z from the complement of bit `bit`,
+    /// always sets H, clears N, and leaves carry untouched.
+    fn bit_test(&mut self, bit: u8, value: u8) {
+        let mut flags = (self.regs.get_flags() & C_FLAG) | H_FLAG;
+        if value & (1 << bit) == 0 { flags.insert(Z_FLAG); }
+        self.regs.set_flags(flags);
+    }
+
+    // ---- stack and control flow --------------------------------------------
+
+    fn push(&mut self, mem: &mut Memory, reg: Register) {
+        let value = self.regs.read_dword(reg);
+        let sp = self.regs.read_dword(Register::SP).wrapping_sub(2);
+        mem.write_word(sp.wrapping_add(1), (value >> 8) as u8);
+        mem.write_word(sp, (value & 0xFF) as u8);
+        self.regs.write_dword(Register::SP, sp);
+    }
+
+    fn pop(&mut self, mem: &mut Memory, reg: Register) {
+        let sp = self.regs.read_dword(Register::SP);
+        let low = mem.read_word(sp) as u16;
+        let high = mem.read_word(sp.wrapping_add(1)) as u16;
+        self.regs.write_dword(reg, (high << 8) | low);
+        self.regs.write_dword(Register::SP, sp.wrapping_add(2));
+    }
+
+    fn jr(&mut self, offset: i8) {
+        let pc = self.regs.read_dword(Register::PC);
+        let target = (pc as i32 + offset as i32) as u16;
+        self.regs.write_dword(Register::PC, target);
+    }
+
+    fn jr_cc(&mut self, mem: &mut Memory, cond: Condition) -> u8 {
+        let offset = self.fetch_byte(mem) as i8;
+        if self.regs.get_flags().satisfies(cond) {
+            self.jr(offset);
+            12
+        } else {
+            8
+        }
+    }
+
+    fn jp_cc(&mut self, mem: &mut Memory, cond: Condition) -> u8 {
+        let addr = self.fetch_word(mem);
+        if self.regs.get_flags().satisfies(cond) {
+            self.regs.write_dword(Register::PC, addr);
+            16
+        } else {
+            12
+        }
+    }
+
+    fn call(&mut self, mem: &mut Memory, addr: u16) {
+        self.push(mem, Register::PC);
+        self.regs.write_dword(Register::PC, addr);
+    }
+
+    fn call_cc(&mut self, mem: &mut Memory, cond: Condition) -> u8 {
+        let addr = self.fetch_word(mem);
+        if self.regs.get_flags().satisfies(cond) {
+            self.call(mem, addr);
+            24
+        } else {
+            12
+        }
+    }
+
+    fn ret(&mut self, mem: &mut Memory) {
+        self.pop(mem, Register::PC);
+    }
+
+    fn ret_cc(&mut self, mem: &mut Memory, cond: Condition) -> u8 {
+        if self.regs.get_flags().satisfies(cond) {
+            self.ret(mem);
+            20
+        } else {
+            8
+        }
+    }
+
+    /// Decodes and executes a single unprefixed opcode, returning its
+    /// cycle cost.
+    fn execute(&mut self, opcode: u8, mem: &mut Memory) -> u8 {
+        match opcode {
+            0x00 => 4,
+
+            // 8-bit register/(HL)-to-register/(HL) loads.
+            0x40..=0x7F if opcode != 0x76 => {
+                let dst = (opcode >> 3) & 0x07;
+                let src = opcode & 0x07;
+                let value = self.read_r8(src, mem);
+                self.write_r8(dst, mem, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+            0x76 => MAIN_TABLE[opcode as usize].cycles,
+
+            // LD r,d8 / LD (HL),d8.
+            0x06 | 0x0E | 0x16 | 0x1E | 0x26 | 0x2E | 0x36 | 0x3E => {
+                let dst = (opcode >> 3) & 0x07;
+                let value = self.fetch_byte(mem);
+                self.write_r8(dst, mem, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // ADD/ADC/SUB/SBC/AND/XOR/OR/CP A,r.
+            0x80..=0xBF => {
+                let op = (opcode >> 3) & 0x07;
+                let value = self.read_r8(opcode & 0x07, mem);
+                self.alu_op(op, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // ADD/ADC/SUB/SBC/AND/XOR/OR/CP A,d8.
+            0xC6 | 0xCE | 0xD6 | 0xDE | 0xE6 | 0xEE | 0xF6 | 0xFE => {
+                let op = (opcode >> 3) & 0x07;
+                let value = self.fetch_byte(mem);
+                self.alu_op(op, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // LD BC/DE/HL/SP,d16.
+            0x01 | 0x11 | 0x21 | 0x31 => {
+                let reg = Self::dword_reg(opcode);
+                let value = self.fetch_word(mem);
+                self.regs.write_dword(reg, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // INC BC/DE/HL/SP.
+            0x03 | 0x13 | 0x23 | 0x33 => {
+                let reg = Self::dword_reg(opcode);
+                let value = self.regs.read_dword(reg).wrapping_add(1);
+                self.regs.write_dword(reg, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // DEC BC/DE/HL/SP.
+            0x0B | 0x1B | 0x2B | 0x3B => {
+                let reg = Self::dword_reg(opcode);
+                let value = self.regs.read_dword(reg).wrapping_sub(1);
+                self.regs.write_dword(reg, value);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // INC r8 / INC (HL).
+            0x04 | 0x0C | 0x14 | 0x1C | 0x24 | 0x2C | 0x34 | 0x3C => {
+                let dst = (opcode >> 3) & 0x07;
+                let value = self.read_r8(dst, mem);
+                let result = self.inc8(value);
+                self.write_r8(dst, mem, result);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // DEC r8 / DEC (HL).
+            0x05 | 0x0D | 0x15 | 0x1D | 0x25 | 0x2D | 0x35 | 0x3D => {
+                let dst = (opcode >> 3) & 0x07;
+                let value = self.read_r8(dst, mem);
+                let result = self.dec8(value);
+                self.write_r8(dst, mem, result);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // LD (BC/DE),A and LD A,(BC/DE).
+            0x02 => { let addr = self.regs.read_dword(Register::BC); let a = self.regs.read_word(Register::A); mem.write_word(addr, a); 8 }
+            0x12 => { let addr = self.regs.read_dword(Register::DE); let a = self.regs.read_word(Register::A); mem.write_word(addr, a); 8 }
+            0x0A => { let addr = self.regs.read_dword(Register::BC); let v = mem.read_word(addr); self.regs.write_word(Register::A, v); 8 }
+            0x1A => { let addr = self.regs.read_dword(Register::DE); let v = mem.read_word(addr); self.regs.write_word(Register::A, v); 8 }
+
+            // LD (HL+/-),A and LD A,(HL+/-).
+            0x22 => {
+                let addr = self.regs.read_dword(Register::HL);
+                let a = self.regs.read_word(Register::A);
+                mem.write_word(addr, a);
+                self.regs.write_dword(Register::HL, addr.wrapping_add(1));
+                8
+            }
+            0x32 => {
+                let addr = self.regs.read_dword(Register::HL);
+                let a = self.regs.read_word(Register::A);
+                mem.write_word(addr, a);
+                self.regs.write_dword(Register::HL, addr.wrapping_sub(1));
+                8
+            }
+            0x2A => {
+                let addr = self.regs.read_dword(Register::HL);
+                let v = mem.read_word(addr);
+                self.regs.write_word(Register::A, v);
+                self.regs.write_dword(Register::HL, addr.wrapping_add(1));
+                8
+            }
+            0x3A => {
+                let addr = self.regs.read_dword(Register::HL);
+                let v = mem.read_word(addr);
+                self.regs.write_word(Register::A, v);
+                self.regs.write_dword(Register::HL, addr.wrapping_sub(1));
+                8
+            }
+
+            // LD (a16),A / LD A,(a16) and the 0xFF00+n / 0xFF00+C variants.
+            0xEA => { let addr = self.fetch_word(mem); let a = self.regs.read_word(Register::A); mem.write_word(addr, a); 16 }
+            0xFA => { let addr = self.fetch_word(mem); let v = mem.read_word(addr); self.regs.write_word(Register::A, v); 16 }
+            0xE0 => { let off = self.fetch_byte(mem) as u16; let a = self.regs.read_word(Register::A); mem.write_word(0xFF00 + off, a); 12 }
+            0xF0 => { let off = self.fetch_byte(mem) as u16; let v = mem.read_word(0xFF00 + off); self.regs.write_word(Register::A, v); 12 }
+            0xE2 => { let c = self.regs.read_word(Register::C) as u16; let a = self.regs.read_word(Register::A); mem.write_word(0xFF00 + c, a); 8 }
+            0xF2 => { let c = self.regs.read_word(Register::C) as u16; let v = mem.read_word(0xFF00 + c); self.regs.write_word(Register::A, v); 8 }
+
+            // PUSH/POP.
+            0xC5 => { self.push(mem, Register::BC); 16 }
+            0xD5 => { self.push(mem, Register::DE); 16 }
+            0xE5 => { self.push(mem, Register::HL); 16 }
+            0xF5 => { self.push(mem, Register::AF); 16 }
+            0xC1 => { self.pop(mem, Register::BC); 12 }
+            0xD1 => { self.pop(mem, Register::DE); 12 }
+            0xE1 => { self.pop(mem, Register::HL); 12 }
+            0xF1 => {
+                self.pop(mem, Register::AF);
+                // The low nibble of F is always zero; a popped value could
+                // set it from stack garbage, which would make a later
+                // `get_flags` panic.
+                let af = self.regs.read_dword(Register::AF) & 0xFFF0;
+                self.regs.write_dword(Register::AF, af);
+                12
+            }
+
+            // JP/JR, unconditional and conditional.
+            0xC3 => { let addr = self.fetch_word(mem); self.regs.write_dword(Register::PC, addr); 16 }
+            0xC2 => self.jp_cc(mem, Condition::NZ),
+            0xCA => self.jp_cc(mem, Condition::Z),
+            0xD2 => self.jp_cc(mem, Condition::NC),
+            0xDA => self.jp_cc(mem, Condition::C),
+            0xE9 => { let addr = self.regs.read_dword(Register::HL); self.regs.write_dword(Register::PC, addr); 4 }
+            0x18 => { let offset = self.fetch_byte(mem) as i8; self.jr(offset); 12 }
+            0x20 => self.jr_cc(mem, Condition::NZ),
+            0x28 => self.jr_cc(mem, Condition::Z),
+            0x30 => self.jr_cc(mem, Condition::NC),
+            0x38 => self.jr_cc(mem, Condition::C),
+
+            // CALL/RET, unconditional and conditional.
+            0xCD => { let addr = self.fetch_word(mem); self.call(mem, addr); 24 }
+            0xC4 => self.call_cc(mem, Condition::NZ),
+            0xCC => self.call_cc(mem, Condition::Z),
+            0xD4 => self.call_cc(mem, Condition::NC),
+            0xDC => self.call_cc(mem, Condition::C),
+            0xC9 => { self.ret(mem); 16 }
+            0xD9 => { self.ret(mem); self.ime = true; 16 }
+
+            // DI/EI. Real hardware delays EI's effect until after the
+            // instruction following it; this CPU enables it immediately,
+            // which is a simplification.
+            0xF3 => { self.ime = false; 4 }
+            0xFB => { self.ime = true; 4 }
+
+            0xC0 => self.ret_cc(mem, Condition::NZ),
+            0xC8 => self.ret_cc(mem, Condition::Z),
+            0xD0 => self.ret_cc(mem, Condition::NC),
+            0xD8 => self.ret_cc(mem, Condition::C),
+
+            // RST n: push PC, jump to the fixed low-page vector the opcode
+            // encodes in its middle three bits.
+            0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => {
+                let target = (opcode & 0x38) as u16;
+                self.call(mem, target);
+                16
+            }
+
+            // ADD HL,rr.
+            0x09 | 0x19 | 0x29 | 0x39 => {
+                let reg = Self::dword_reg(opcode);
+                self.add_hl(reg);
+                MAIN_TABLE[opcode as usize].cycles
+            }
+
+            // Accumulator rotates.
+            0x07 => { self.rlca(); 4 }
+            0x0F => { self.rrca(); 4 }
+            0x17 => { self.rla(); 4 }
+            0x1F => { self.rra(); 4 }
+
+            // DAA/CPL/SCF/CCF.
+            0x27 => { self.daa(); 4 }
+            0x2F => {
+                let a = self.regs.read_word(Register::A);
+                self.regs.write_word(Register::A, !a);
+                let flags = self.regs.get_flags() | N_FLAG | H_FLAG;
+                self.regs.set_flags(flags);
+                4
+            }
+            0x37 => {
+                let mut flags = self.regs.get_flags() & Z_FLAG;
+                flags.insert(C_FLAG);
+                self.regs.set_flags(flags);
+                4
+            }
+            0x3F => {
+                let flags = self.regs.get_flags();
+                let mut result = flags & Z_FLAG;
+                if !flags.contains(C_FLAG) { result.insert(C_FLAG); }
+                self.regs.set_flags(result);
+                4
+            }
+
+            // LD (a16),SP.
+            0x08 => {
+                let addr = self.fetch_word(mem);
+                let sp = self.regs.read_dword(Register::SP);
+                mem.write_word(addr, (sp & 0xFF) as u8);
+                mem.write_word(addr.wrapping_add(1), (sp >> 8) as u8);
+                20
+            }
+
+            // LD HL,SP+r8.
+            0xF8 => {
+                let sp = self.regs.read_dword(Register::SP);
+                let offset = self.fetch_byte(mem) as i8;
+                let result = self.add_sp_offset(sp, offset);
+                self.regs.write_dword(Register::HL, result);
+                12
+            }
+
+            // LD SP,HL.
+            0xF9 => {
+                let hl = self.regs.read_dword(Register::HL);
+                self.regs.write_dword(Register::SP, hl);
+                8
+            }
+
+            // ADD SP,r8.
+            0xE8 => {
+                let sp = self.regs.read_dword(Register::SP);
+                let offset = self.fetch_byte(mem) as i8;
+                let result = self.add_sp_offset(sp, offset);
+                self.regs.write_dword(Register::SP, result);
+                16
+            }
+
+            // STOP: halts the CPU and LCD until a button is pressed. This
+            // emulator has no low-power mode to enter, so it's treated as a
+            // no-op that still consumes its trailing (always zero) byte.
+            0x10 => {
+                self.fetch_byte(mem);
+                4
+            }
+
+            // Genuinely undefined opcodes. Real hardware locks up the CPU
+            // on these; rather than crash the whole interpreter on a stray
+            // byte, treat them as a NOP so execution can continue.
+            0xD3 | 0xDB | 0xDD | 0xE3 | 0xE4 | 0xEB | 0xEC | 0xED | 0xF4 | 0xFC | 0xFD => 4,
+
+            _ => panic!("Unimplemented opcode: 0x{:02x} ({})", opcode, MAIN_TABLE[opcode as usize].mnemonic),
+        }
+    }
+
+    /// Decodes and executes a single `0xCB`-prefixed opcode, returning its
+    /// cycle cost. The opcode space is fully regular: the low three bits
+    /// select the register (or `(HL)`) via `REG_TABLE`, and the remaining
+    /// bits select a rotate/shift operation (`0x00..=0x3F`), a bit index to
+    /// test (`0x40..=0x7F`), reset (`0x80..=0xBF`), or set (`0xC0..=0xFF`).
+    fn execute_cb(&mut self, opcode: u8, mem: &mut Memory) -> u8 {
+        let reg_bits = opcode & 0x07;
+        let bit = (opcode >> 3) & 0x07;
+
+        match opcode {
+            0x00..=0x07 => { let v = self.read_r8(reg_bits, mem); let r = self.rlc(v); self.write_r8(reg_bits, mem, r); }
+            0x08..=0x0F => { let v = self.read_r8(reg_bits, mem); let r = self.rrc(v); self.write_r8(reg_bits, mem, r); }
+            0x10..=0x17 => { let v = self.read_r8(reg_bits, mem); let r = self.rl(v); self.write_r8(reg_bits, mem, r); }
+            0x18..=0x1F => { let v = self.read_r8(reg_bits, mem); let r = self.rr(v); self.write_r8(reg_bits, mem, r); }
+            0x20..=0x27 => { let v = self.read_r8(reg_bits, mem); let r = self.sla(v); self.write_r8(reg_bits, mem, r); }
+            0x28..=0x2F => { let v = self.read_r8(reg_bits, mem); let r = self.sra(v); self.write_r8(reg_bits, mem, r); }
+            0x30..=0x37 => { let v = self.read_r8(reg_bits, mem); let r = self.swap(v); self.write_r8(reg_bits, mem, r); }
+            0x38..=0x3F => { let v = self.read_r8(reg_bits, mem); let r = self.srl(v); self.write_r8(reg_bits, mem, r); }
+
+            0x40..=0x7F => { let v = self.read_r8(reg_bits, mem); self.bit_test(bit, v); }
+            0x80..=0xBF => { let v = self.read_r8(reg_bits, mem); self.write_r8(reg_bits, mem, v & !(1 << bit)); }
+            0xC0..=0xFF => { let v = self.read_r8(reg_bits, mem); self.write_r8(reg_bits, mem, v | (1 << bit)); }
+        }
+
+        CB_TABLE[opcode as usize].cycles
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Cpu {
+    /// Serializes the CPU's full state (register file and IME) to a byte
+    /// buffer. A complete machine save state is this plus a
+    /// `Memory::snapshot`; the two travel as separate buffers since nothing
+    /// in this tree yet bundles a `Cpu` and a `Memory` into a single
+    /// top-level type.
+    pub fn snapshot(&self) -> Vec<u8> {
+        ::bincode::serialize(self).expect("Cpu state should always be serializable")
+    }
+
+    /// Restores CPU state previously produced by `snapshot`. Fails rather
+    /// than panicking if `data` is truncated, corrupted, or was produced by
+    /// an incompatible version, since save files are expected to be read
+    /// back across sessions where that's a realistic occurrence.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), ::bincode::Error> {
+        *self = ::bincode::deserialize(data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use memory::Memory;
+
+    fn cpu() -> Cpu {
+        Cpu::new(RegDataArray::new([0u8; 12]))
+    }
+
+    fn mem() -> Memory {
+        Memory::new(vec![0u8; 0x8000])
+    }
+
+    #[test]
+    fn adc_sets_half_carry_across_nibble_boundary() {
+        let mut cpu = cpu();
+        cpu.regs.write_word(Register::A, 0x0F);
+        cpu.regs.set_flags(C_FLAG);
+        cpu.add_a(0x01, true);
+        assert_eq!(cpu.regs.read_word(Register::A), 0x11);
+        assert!(cpu.regs.get_flags().contains(H_FLAG));
+        assert!(!cpu.regs.get_flags().contains(C_FLAG));
+    }
+
+    #[test]
+    fn sbc_sets_half_carry_on_nibble_borrow() {
+        let mut cpu = cpu();
+        cpu.regs.write_word(Register::A, 0x10);
+        cpu.regs.set_flags(C_FLAG);
+        let result = cpu.sub_a(0x01, true);
+        assert_eq!(result, 0x0E);
+        assert!(cpu.regs.get_flags().contains(H_FLAG));
+        assert!(!cpu.regs.get_flags().contains(C_FLAG));
+    }
+
+    #[test]
+    fn pop_af_masks_low_nibble_of_f() {
+        let mut cpu = cpu();
+        let mut mem = mem();
+        cpu.regs.write_dword(Register::SP, 0xC000);
+        mem.write_word(0xC000, 0xFF);
+        mem.write_word(0xC001, 0x00);
+        cpu.execute(0xF1, &mut mem);
+        assert_eq!(cpu.regs.read_dword(Register::AF) & 0x0F, 0);
+    }
+
+    #[test]
+    fn jr_nz_costs_more_cycles_when_branch_is_taken() {
+        let mut mem = mem();
+
+        let mut not_taken = cpu();
+        not_taken.regs.set_flags(Z_FLAG);
+        mem.write_word(0x0000, 0x05);
+        assert_eq!(not_taken.execute(0x20, &mut mem), 8);
+
+        let mut taken = cpu();
+        taken.regs.set_flags(StatusFlags::empty());
+        assert_eq!(taken.execute(0x20, &mut mem), 12);
+    }
+
+    #[test]
+    fn service_interrupts_dispatches_the_highest_priority_pending_source() {
+        let mut cpu = cpu();
+        let mut mem = mem();
+        cpu.regs.write_dword(Register::SP, 0xC000);
+        cpu.ime = true;
+        mem.write_word(0xFFFF, 0xFF); // all sources enabled
+        mem.request_interrupt(InterruptSource::Timer);
+        mem.request_interrupt(InterruptSource::VBlank);
+
+        let cycles = cpu.service_interrupts(&mut mem);
+
+        assert_eq!(cycles, 20);
+        assert_eq!(cpu.regs.read_dword(Register::PC), InterruptSource::VBlank.vector());
+        assert_eq!(mem.interrupt_flags() & (1 << InterruptSource::VBlank.bit()), 0);
+        assert_ne!(mem.interrupt_flags() & (1 << InterruptSource::Timer.bit()), 0);
+        assert!(!cpu.ime);
+    }
+
+    #[test]
+    fn service_interrupts_ignores_sources_not_enabled_in_ie() {
+        let mut cpu = cpu();
+        let mut mem = mem();
+        cpu.regs.write_dword(Register::SP, 0xC000);
+        cpu.ime = true;
+        mem.write_word(0xFFFF, 0x00); // nothing enabled
+        mem.request_interrupt(InterruptSource::VBlank);
+
+        assert_eq!(cpu.service_interrupts(&mut mem), 0);
+        assert!(cpu.ime);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn restore_reports_corrupt_snapshots_instead_of_panicking() {
+        let mut cpu = cpu();
+        assert!(cpu.restore(&[0xFF; 4]).is_err());
+    }
+}
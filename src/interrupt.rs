@@ -0,0 +1,44 @@
+/// One of the Gameboy's five interrupt sources, ordered by dispatch
+/// priority: `VBlank` is checked first, `Joypad` last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptSource {
+    VBlank,
+    LcdStat,
+    Timer,
+    Serial,
+    Joypad,
+}
+
+impl InterruptSource {
+    /// All five sources, in priority order from highest to lowest.
+    pub const ALL: [InterruptSource; 5] = [
+        InterruptSource::VBlank,
+        InterruptSource::LcdStat,
+        InterruptSource::Timer,
+        InterruptSource::Serial,
+        InterruptSource::Joypad,
+    ];
+
+    /// This source's bit position in both the IE (`0xFFFF`) and IF
+    /// (`0xFF0F`) registers.
+    pub fn bit(self) -> u8 {
+        match self {
+            InterruptSource::VBlank => 0,
+            InterruptSource::LcdStat => 1,
+            InterruptSource::Timer => 2,
+            InterruptSource::Serial => 3,
+            InterruptSource::Joypad => 4,
+        }
+    }
+
+    /// The address this source's handler is dispatched to.
+    pub fn vector(self) -> u16 {
+        match self {
+            InterruptSource::VBlank => 0x40,
+            InterruptSource::LcdStat => 0x48,
+            InterruptSource::Timer => 0x50,
+            InterruptSource::Serial => 0x58,
+            InterruptSource::Joypad => 0x60,
+        }
+    }
+}
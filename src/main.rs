@@ -1,8 +1,19 @@
 #[macro_use]
 extern crate bitflags;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serde")]
+extern crate bincode;
+
 pub mod register;
 pub mod memory;
+pub mod cartridge;
+pub mod cpu;
+pub mod interrupt;
 
 use register::*;
 use memory::*;
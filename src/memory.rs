@@ -1,10 +1,30 @@
+use cartridge::Cartridge;
+use interrupt::InterruptSource;
+
 /// Represents an address to a location in the Gameboy's memory.
-type Address = u16;
+pub type Address = u16;
+
+/// A component that services reads and writes for some range of the
+/// Gameboy's address space. Implementations return `Err(BusError)` for any
+/// address outside the range they own, rather than panicking, so that a
+/// bus can try several components in turn and the final miss can be
+/// reported with the offending address attached.
+pub trait MemoryBus {
+    /// Reads the byte at `addr`, or fails if this component doesn't own it.
+    fn read(&self, addr: Address) -> Result<u8, BusError>;
+
+    /// Writes `val` to `addr`, or fails if this component doesn't own it.
+    fn write(&mut self, addr: Address, val: u8) -> Result<(), BusError>;
+}
+
+/// The error returned when an address can't be routed to any mapped
+/// component. Carries the offending address for debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusError(pub Address);
 
 // The lowest segment of memory in the Gameboy is cartridge memory.
 const CART_START: Address = 0x0000;
 const CART_END: Address = 0x7FFF;
-const CART_SIZE: usize = 1 + (CART_END - CART_START) as usize;
 
 // After cartridge memory comes video RAM (VRAM).
 const VRAM_START: Address = 0x8000;
@@ -13,20 +33,20 @@ const VRAM_SIZE: usize = 1 + (VRAM_END - VRAM_START) as usize;
 
 // Following VRAM is the RAM present on the cartridge rather than the Gameboy
 // itself. It will be referred to as EXRAM to contrast it with the internal RAM
-// segment which follows it.
+// segment which follows it. Unlike the other segments, EXRAM lives on the
+// cartridge and is owned by the `Cartridge` type rather than `Memory` itself.
 const EXRAM_START: Address = 0xA000;
 const EXRAM_END: Address = 0xBFFF;
-const EXRAM_SIZE: usize = 1 + (EXRAM_END - EXRAM_START) as usize;
 
-// The segment after external RAM is internal (to the Gameboy itself) working 
-// RAM. It will be referred to as INRAM to contrast it with EXRAM as defined 
+// The segment after external RAM is internal (to the Gameboy itself) working
+// RAM. It will be referred to as INRAM to contrast it with EXRAM as defined
 // above.
 const INRAM_START: Address = 0xC000;
 const INRAM_END: Address = 0xDFFF;
 const INRAM_SIZE: usize = 1 + (INRAM_END - INRAM_START) as usize;
 
 // After internal RAM is the "echo" RAM segment. The contents of this segment
-// are always bit-identical to the contents of INRAM. For emulation, any 
+// are always bit-identical to the contents of INRAM. For emulation, any
 // operations on this segment should instead be performed on the INRAM segment
 // directly preceeding it as there is no need to simulate two identical memory
 // segments separately.
@@ -39,7 +59,12 @@ const OAM_START: Address = 0xFE00;
 const OAM_END: Address = 0xFE9F;
 const OAM_SIZE: usize = 1 + (OAM_END - OAM_START) as usize;
 
-// A small gap follows the OAM segment. Memory in that segment is unused.
+// A small gap follows the OAM segment. Memory in that segment is unused; real
+// hardware returns 0xFF (or garbage, depending on PPU mode) for reads and
+// discards writes, rather than faulting.
+const UNUSED_START: Address = 0xFEA0;
+const UNUSED_END: Address = 0xFEFF;
+
 // The next segment of used memory is dedicated to the hardware IO registers.
 const IO_START: Address = 0xFF00;
 const IO_END: Address = 0xFF7F;
@@ -56,60 +81,555 @@ const HRAM_SIZE: usize = 1 + (HRAM_END - HRAM_START) as usize;
 /// The address of the interrupt-enable flag.
 const INTERRUPT_ENABLE: Address = 0xFFFF;
 
+/// The size, in bytes, of the boot ROM.
+const BOOT_ROM_SIZE: usize = 0x100;
+
+/// The last address shadowed by the boot ROM overlay while it is mapped in.
+const BOOT_ROM_END: Address = 0x00FF;
+
+/// The IO register which, on a non-zero write, permanently unmaps the boot
+/// ROM overlay. This matches the real hardware's boot-ROM disable latch:
+/// once unmapped, it cannot be remapped without a reset.
+const BOOT_ROM_DISABLE: Address = 0xFF50;
+
+/// The address of the interrupt-flag register: bits set here, and also set
+/// in the IE register at `INTERRUPT_ENABLE`, are dispatched by
+/// `Cpu::service_interrupts`.
+const IF_REGISTER: Address = 0xFF0F;
+
+/// The OAM DMA register. Writing a high byte `XX` here latches
+/// `0xXX00..=0xXX9F` as the transfer's source page; see `OamDma`.
+const DMA_REGISTER: Address = 0xFF46;
+
+/// The video RAM memory segment (`0x8000..=0x9FFF`), as a standalone
+/// component so the eventual PPU can own and address it directly.
+pub struct VideoRam {
+    data: [u8; VRAM_SIZE],
+}
+
+impl VideoRam {
+    fn new() -> VideoRam {
+        VideoRam { data: [0; VRAM_SIZE] }
+    }
+}
+
+impl MemoryBus for VideoRam {
+    fn read(&self, addr: Address) -> Result<u8, BusError> {
+        match addr {
+            VRAM_START..=VRAM_END => Ok(self.data[(addr - VRAM_START) as usize]),
+            _ => Err(BusError(addr)),
+        }
+    }
+
+    fn write(&mut self, addr: Address, val: u8) -> Result<(), BusError> {
+        match addr {
+            VRAM_START..=VRAM_END => {
+                self.data[(addr - VRAM_START) as usize] = val;
+                Ok(())
+            }
+            _ => Err(BusError(addr)),
+        }
+    }
+}
+
+// `serde`'s derive only covers fixed-size arrays up to length 32, so VRAM's
+// 8 KiB backing array needs a hand-written impl that serializes it as a
+// plain byte sequence instead.
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for VideoRam {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::serde::Serializer
+    {
+        serializer.serialize_bytes(&self.data)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for VideoRam {
+    fn deserialize<D>(deserializer: D) -> Result<VideoRam, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        let bytes = <Vec<u8> as ::serde::Deserialize>::deserialize(deserializer)?;
+        let mut vram = VideoRam::new();
+        let len = vram.data.len().min(bytes.len());
+        vram.data[..len].copy_from_slice(&bytes[..len]);
+        Ok(vram)
+    }
+}
+
+/// The Object-Attribute Memory segment (`0xFE00..=0xFE9F`), as a standalone
+/// component so the eventual PPU (and OAM DMA) can own and address it
+/// directly.
+pub struct Oam {
+    data: [u8; OAM_SIZE],
+}
+
+impl Oam {
+    fn new() -> Oam {
+        Oam { data: [0; OAM_SIZE] }
+    }
+}
+
+impl MemoryBus for Oam {
+    fn read(&self, addr: Address) -> Result<u8, BusError> {
+        match addr {
+            OAM_START..=OAM_END => Ok(self.data[(addr - OAM_START) as usize]),
+            _ => Err(BusError(addr)),
+        }
+    }
+
+    fn write(&mut self, addr: Address, val: u8) -> Result<(), BusError> {
+        match addr {
+            OAM_START..=OAM_END => {
+                self.data[(addr - OAM_START) as usize] = val;
+                Ok(())
+            }
+            _ => Err(BusError(addr)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Oam {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::serde::Serializer
+    {
+        serializer.serialize_bytes(&self.data)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Oam {
+    fn deserialize<D>(deserializer: D) -> Result<Oam, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        let bytes = <Vec<u8> as ::serde::Deserialize>::deserialize(deserializer)?;
+        let mut oam = Oam::new();
+        let len = oam.data.len().min(bytes.len());
+        oam.data[..len].copy_from_slice(&bytes[..len]);
+        Ok(oam)
+    }
+}
+
+/// The hardware IO register segment (`0xFF00..=0xFF7F`), as a standalone
+/// component so the eventual timer/serial/PPU/sound modules can own and
+/// address their own registers directly.
+pub struct IoRegisters {
+    data: [u8; IO_SIZE],
+}
+
+impl IoRegisters {
+    fn new() -> IoRegisters {
+        IoRegisters { data: [0; IO_SIZE] }
+    }
+}
+
+impl MemoryBus for IoRegisters {
+    fn read(&self, addr: Address) -> Result<u8, BusError> {
+        match addr {
+            IO_START..=IO_END => Ok(self.data[(addr - IO_START) as usize]),
+            _ => Err(BusError(addr)),
+        }
+    }
+
+    fn write(&mut self, addr: Address, val: u8) -> Result<(), BusError> {
+        match addr {
+            IO_START..=IO_END => {
+                self.data[(addr - IO_START) as usize] = val;
+                Ok(())
+            }
+            _ => Err(BusError(addr)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for IoRegisters {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::serde::Serializer
+    {
+        serializer.serialize_bytes(&self.data)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for IoRegisters {
+    fn deserialize<D>(deserializer: D) -> Result<IoRegisters, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        let bytes = <Vec<u8> as ::serde::Deserialize>::deserialize(deserializer)?;
+        let mut io = IoRegisters::new();
+        let len = io.data.len().min(bytes.len());
+        io.data[..len].copy_from_slice(&bytes[..len]);
+        Ok(io)
+    }
+}
+
+/// A minimal OAM DMA unit. Writing to `DMA_REGISTER` latches the high byte
+/// of a source address and starts a transfer; `dma_tick` then copies one
+/// byte per call from `source:offset` into `OAM_START + offset`, matching
+/// real hardware's 160-machine-cycle transfer.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct OamDma {
+    /// The latched source page (forms the high byte of the source address).
+    source: u8,
+    /// How many bytes have been copied so far this transfer.
+    offset: u8,
+    /// Whether a transfer is currently in progress.
+    active: bool,
+}
+
+impl OamDma {
+    fn new() -> OamDma {
+        OamDma { source: 0, offset: 0, active: false }
+    }
+
+    /// Latches `source_page` and (re)starts a transfer from its beginning,
+    /// as happens on every write to `DMA_REGISTER`, including one that
+    /// interrupts a transfer already in progress.
+    fn start(&mut self, source_page: u8) {
+        self.source = source_page;
+        self.offset = 0;
+        self.active = true;
+    }
+}
+
 /// Represents the full memory space available to a Gameboy.
 pub struct Memory {
-    /// The cartridge ROM memory segment.
-    cart: [u8; CART_SIZE],
+    /// The cartridge, owning ROM and any external (battery-backed) RAM.
+    cart: Cartridge,
     /// The video RAM memory segment.
-    vram: [u8; VRAM_SIZE],
-    /// The external catridge RAM segment.
-    exram: [u8; EXRAM_SIZE],
+    vram: VideoRam,
     /// The internal working RAM segment.
     inram: [u8; INRAM_SIZE],
     /// The Object-Attribute Memory segment.
-    oam: [u8; OAM_SIZE],
+    oam: Oam,
     /// The IO registers segment.
-    io: [u8; IO_SIZE],
+    io: IoRegisters,
     /// The "high" working RAM segment.
     hram: [u8; HRAM_SIZE],
     /// The interrupt-enable flags byte.
     interrupt: u8,
+    /// The boot ROM overlay, shadowing `0x0000..=0x00FF` until it is
+    /// unmapped by a write to `BOOT_ROM_DISABLE`. `None` if no boot ROM was
+    /// loaded, or once it has been unmapped.
+    boot_rom: Option<[u8; BOOT_ROM_SIZE]>,
+    /// The OAM DMA unit.
+    dma: OamDma,
 }
 
 impl Memory {
+    /// Builds a fresh `Memory` around the given cartridge ROM image, with
+    /// every other segment zeroed and no boot ROM mapped in.
+    pub fn new(rom: Vec<u8>) -> Memory {
+        Memory {
+            cart: Cartridge::new(rom),
+            vram: VideoRam::new(),
+            inram: [0; INRAM_SIZE],
+            oam: Oam::new(),
+            io: IoRegisters::new(),
+            hram: [0; HRAM_SIZE],
+            interrupt: 0,
+            boot_rom: None,
+            dma: OamDma::new(),
+        }
+    }
+
+    /// Maps a boot ROM image in over `0x0000..=0x00FF`. It stays mapped
+    /// until a non-zero write to the boot-ROM disable register
+    /// (`0xFF50`) unmaps it, matching real hardware.
+    pub fn load_boot_rom(&mut self, boot_rom: [u8; BOOT_ROM_SIZE]) {
+        self.boot_rom = Some(boot_rom);
+    }
+
+    /// Reads a byte, treating any address this bus can't route as an
+    /// unmapped read (`0xFF`). Use `MemoryBus::read` directly if you need
+    /// to observe the failure.
     pub fn read_word(&self, addr: Address) -> u8 {
+        self.read(addr).unwrap_or(0xFF)
+    }
+
+    /// Writes a byte, silently discarding it if this bus can't route the
+    /// address. Use `MemoryBus::write` directly if you need to observe the
+    /// failure.
+    pub fn write_word(&mut self, addr: Address, data: u8) {
+        let _ = self.write(addr, data);
+    }
+
+    /// Whether the cartridge has battery-backed RAM worth persisting
+    /// between sessions.
+    pub fn has_battery_ram(&self) -> bool {
+        self.cart.has_battery_ram()
+    }
+
+    /// The current contents of the cartridge's battery-backed RAM, for
+    /// writing out to a save file.
+    pub fn save_ram(&self) -> &[u8] {
+        self.cart.save_ram()
+    }
+
+    /// Restores the cartridge's battery-backed RAM from a previously saved
+    /// buffer, e.g. loaded from disk alongside the ROM.
+    pub fn load_ram(&mut self, data: &[u8]) {
+        self.cart.load_ram(data);
+    }
+
+    /// The current IE (`0xFFFF`) register value.
+    pub fn interrupt_enable(&self) -> u8 {
+        self.interrupt
+    }
+
+    /// The current IF (`0xFF0F`) register value.
+    pub fn interrupt_flags(&self) -> u8 {
+        self.read_word(IF_REGISTER)
+    }
+
+    /// Sets `source`'s bit in the IF register, requesting that interrupt.
+    /// Hardware components (timer, PPU, serial, joypad) call this to
+    /// signal an interrupt; actual dispatch happens in
+    /// `Cpu::service_interrupts`.
+    pub fn request_interrupt(&mut self, source: InterruptSource) {
+        let if_reg = self.interrupt_flags();
+        self.write_word(IF_REGISTER, if_reg | (1 << source.bit()));
+    }
+
+    /// Clears `source`'s bit in the IF register, e.g. once it has been
+    /// dispatched.
+    pub fn clear_interrupt(&mut self, source: InterruptSource) {
+        let if_reg = self.interrupt_flags();
+        self.write_word(IF_REGISTER, if_reg & !(1 << source.bit()));
+    }
+
+    /// Whether an OAM DMA transfer is currently in progress. While true,
+    /// real hardware restricts the CPU to accessing HRAM only; the eventual
+    /// CPU integration is responsible for enforcing that.
+    pub fn dma_in_progress(&self) -> bool {
+        self.dma.active
+    }
+
+    /// Advances an in-progress OAM DMA transfer by one byte, copying
+    /// `source:offset` into `OAM_START + offset`. Does nothing if no
+    /// transfer is in progress. A real transfer copies one byte per machine
+    /// cycle over 160 cycles, so this is meant to be called once per
+    /// machine cycle for as long as `dma_in_progress` holds.
+    pub fn dma_tick(&mut self) {
+        if !self.dma.active {
+            return;
+        }
+
+        let src = ((self.dma.source as Address) << 8) | self.dma.offset as Address;
+        let dst = OAM_START + self.dma.offset as Address;
+        let byte = self.read_word(src);
+        self.write_word(dst, byte);
+
+        self.dma.offset += 1;
+        if self.dma.offset as usize >= OAM_SIZE {
+            self.dma.active = false;
+        }
+    }
+}
+
+impl MemoryBus for Memory {
+    fn read(&self, addr: Address) -> Result<u8, BusError> {
+        if addr <= BOOT_ROM_END {
+            if let Some(ref boot_rom) = self.boot_rom {
+                return Ok(boot_rom[addr as usize]);
+            }
+        }
+
         match addr {
             // Handle address-specific reads
-            INTERRUPT_ENABLE => self.interrupt,
-
-            CART_START ... CART_END => self.cart[addr as usize],
-            VRAM_START ... VRAM_END => self.vram[(addr - VRAM_START) as usize],
-            EXRAM_START ... EXRAM_END => self.exram[(addr - EXRAM_START) as usize],
-            INRAM_START ... INRAM_END => self.inram[(addr - INRAM_START) as usize],
-            ERAM_START ... ERAM_END => self.inram[(addr - ERAM_START) as usize],
-            OAM_START ... OAM_END => self.oam[(addr - OAM_START) as usize],
-            IO_START ... IO_END => self.io[(addr - IO_START) as usize],
-            HRAM_START ... HRAM_END => self.hram[(addr - HRAM_START) as usize],
+            INTERRUPT_ENABLE => Ok(self.interrupt),
 
-            _ => panic!("Cannot read memory from location: 0x{:4x}", addr)
+            CART_START..=CART_END => Ok(self.cart.read_rom(addr)),
+            VRAM_START..=VRAM_END => self.vram.read(addr),
+            EXRAM_START..=EXRAM_END => Ok(self.cart.read_ram(addr)),
+            INRAM_START..=INRAM_END => Ok(self.inram[(addr - INRAM_START) as usize]),
+            ERAM_START..=ERAM_END => Ok(self.inram[(addr - ERAM_START) as usize]),
+            OAM_START..=OAM_END => self.oam.read(addr),
+            UNUSED_START..=UNUSED_END => Ok(0xFF),
+            IO_START..=IO_END => self.io.read(addr),
+            HRAM_START..=HRAM_END => Ok(self.hram[(addr - HRAM_START) as usize]),
         }
     }
 
-    pub fn write_word(&mut self, addr: Address, data: u8) {
+    fn write(&mut self, addr: Address, data: u8) -> Result<(), BusError> {
+        if addr == BOOT_ROM_DISABLE && data != 0 {
+            self.boot_rom = None;
+        }
+
+        if addr == DMA_REGISTER {
+            self.dma.start(data);
+        }
+
         match addr {
             // Handle address-specific writes
-            INTERRUPT_ENABLE => self.interrupt = data,
+            INTERRUPT_ENABLE => { self.interrupt = data; Ok(()) }
 
-            CART_START ... CART_END => self.cart[addr as usize] = data,
-            VRAM_START ... VRAM_END => self.vram[(addr - VRAM_START) as usize] = data,
-            EXRAM_START ... EXRAM_END => self.exram[(addr - EXRAM_START) as usize] = data,
-            INRAM_START ... INRAM_END => self.inram[(addr - INRAM_START) as usize] = data,
-            ERAM_START ... ERAM_END => self.inram[(addr - ERAM_START) as usize] = data,
-            OAM_START ... OAM_END => self.oam[(addr - OAM_START) as usize] = data,
-            IO_START ... IO_END => self.io[(addr - IO_START) as usize] = data,
-            HRAM_START ... HRAM_END => self.hram[(addr - HRAM_START) as usize] = data,
+            CART_START..=CART_END => { self.cart.write_rom(addr, data); Ok(()) }
+            VRAM_START..=VRAM_END => self.vram.write(addr, data),
+            EXRAM_START..=EXRAM_END => { self.cart.write_ram(addr, data); Ok(()) }
+            INRAM_START..=INRAM_END => { self.inram[(addr - INRAM_START) as usize] = data; Ok(()) }
+            ERAM_START..=ERAM_END => { self.inram[(addr - ERAM_START) as usize] = data; Ok(()) }
+            OAM_START..=OAM_END => self.oam.write(addr, data),
+            UNUSED_START..=UNUSED_END => Ok(()),
+            IO_START..=IO_END => self.io.write(addr, data),
+            HRAM_START..=HRAM_END => { self.hram[(addr - HRAM_START) as usize] = data; Ok(()) }
+        }
+    }
+}
+
+// `inram` and `hram` are plain `[u8; N]` fields (not wrapped in their own
+// component type, unlike VRAM/OAM/IO), and serde's derive only covers
+// fixed-size arrays up to length 32. `Memory` therefore gets a
+// hand-written `Serialize`/`Deserialize` pair, built around borrowed and
+// owned mirror structs so the big arrays travel as plain byte sequences.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct MemorySnapshotRef<'a> {
+    cart: &'a Cartridge,
+    vram: &'a VideoRam,
+    inram: &'a [u8],
+    oam: &'a Oam,
+    io: &'a IoRegisters,
+    hram: &'a [u8],
+    interrupt: u8,
+    boot_rom: Option<&'a [u8]>,
+    dma: &'a OamDma,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct MemorySnapshotOwned {
+    cart: Cartridge,
+    vram: VideoRam,
+    inram: Vec<u8>,
+    oam: Oam,
+    io: IoRegisters,
+    hram: Vec<u8>,
+    interrupt: u8,
+    boot_rom: Option<Vec<u8>>,
+    dma: OamDma,
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Memory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::serde::Serializer
+    {
+        let snapshot = MemorySnapshotRef {
+            cart: &self.cart,
+            vram: &self.vram,
+            inram: &self.inram,
+            oam: &self.oam,
+            io: &self.io,
+            hram: &self.hram,
+            interrupt: self.interrupt,
+            boot_rom: self.boot_rom.as_ref().map(|rom| &rom[..]),
+            dma: &self.dma,
+        };
+        snapshot.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Memory {
+    fn deserialize<D>(deserializer: D) -> Result<Memory, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        let snapshot = MemorySnapshotOwned::deserialize(deserializer)?;
+
+        let mut inram = [0u8; INRAM_SIZE];
+        let len = inram.len().min(snapshot.inram.len());
+        inram[..len].copy_from_slice(&snapshot.inram[..len]);
+
+        let mut hram = [0u8; HRAM_SIZE];
+        let len = hram.len().min(snapshot.hram.len());
+        hram[..len].copy_from_slice(&snapshot.hram[..len]);
 
-            _ => panic!("Cannot write to memory location: 0x{:4x}", addr)
+        let boot_rom = snapshot.boot_rom.map(|rom| {
+            let mut arr = [0u8; BOOT_ROM_SIZE];
+            let len = arr.len().min(rom.len());
+            arr[..len].copy_from_slice(&rom[..len]);
+            arr
+        });
+
+        Ok(Memory {
+            cart: snapshot.cart,
+            vram: snapshot.vram,
+            inram: inram,
+            oam: snapshot.oam,
+            io: snapshot.io,
+            hram: hram,
+            interrupt: snapshot.interrupt,
+            boot_rom: boot_rom,
+            dma: snapshot.dma,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Memory {
+    /// Serializes the full memory state (ROM/RAM bank state, VRAM, work
+    /// RAM, OAM, IO registers, HRAM, IE, and boot-ROM overlay) to a byte
+    /// buffer, e.g. for an instant save or a rewind buffer entry.
+    pub fn snapshot(&self) -> Vec<u8> {
+        ::bincode::serialize(self).expect("Memory state should always be serializable")
+    }
+
+    /// Restores memory state previously produced by `snapshot`. Fails rather
+    /// than panicking if `data` is truncated, corrupted, or was produced by
+    /// an incompatible version, since save files are expected to be read
+    /// back across sessions where that's a realistic occurrence.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), ::bincode::Error> {
+        *self = ::bincode::deserialize(data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dma_tick_copies_the_source_page_into_oam_one_byte_at_a_time() {
+        let mut mem = Memory::new(vec![0u8; 0x8000]);
+
+        for i in 0..OAM_SIZE {
+            mem.write_word(0xC000 + i as Address, i as u8);
+        }
+        mem.write_word(DMA_REGISTER, 0xC0);
+        assert!(mem.dma_in_progress());
+
+        for _ in 0..OAM_SIZE {
+            assert!(mem.dma_in_progress());
+            mem.dma_tick();
+        }
+        assert!(!mem.dma_in_progress());
+
+        for i in 0..OAM_SIZE {
+            assert_eq!(mem.read_word(OAM_START + i as Address), i as u8);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn boot_rom_shadows_cart_until_disabled() {
+        let mut rom = vec![0u8; 0x8000];
+        rom[0] = 0x99;
+        let mut mem = Memory::new(rom);
+        mem.load_boot_rom([0x42; BOOT_ROM_SIZE]);
+
+        assert_eq!(mem.read_word(0x0000), 0x42);
+
+        mem.write_word(BOOT_ROM_DISABLE, 0x01);
+
+        assert_eq!(mem.read_word(0x0000), 0x99);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn restore_reports_corrupt_snapshots_instead_of_panicking() {
+        let mut mem = Memory::new(vec![0u8; 0x8000]);
+        assert!(mem.restore(&[0xFF; 4]).is_err());
+    }
+}
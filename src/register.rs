@@ -58,6 +58,35 @@ bitflags! {
     }
 }
 
+/// A Game Boy jump/call/return condition, as encoded in the two condition
+/// bits of opcodes like `JR cc,e8`, `JP cc,a16`, `CALL cc,a16`, and `RET cc`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Condition {
+    /// Not zero: satisfied when `Z_FLAG` is clear.
+    NZ,
+    /// Zero: satisfied when `Z_FLAG` is set.
+    Z,
+    /// No carry: satisfied when `C_FLAG` is clear.
+    NC,
+    /// Carry: satisfied when `C_FLAG` is set.
+    C,
+    /// Always satisfied; used for the unconditional form of these opcodes.
+    Always,
+}
+
+impl StatusFlags {
+    /// Checks whether these flags satisfy a jump/call/return condition.
+    pub fn satisfies(&self, cond: Condition) -> bool {
+        match cond {
+            Condition::NZ => !self.contains(Z_FLAG),
+            Condition::Z => self.contains(Z_FLAG),
+            Condition::NC => !self.contains(C_FLAG),
+            Condition::C => self.contains(C_FLAG),
+            Condition::Always => true,
+        }
+    }
+}
+
 pub trait RegOps {
     /// Get the value of an 8-bit register.
     fn read_word(&self, reg: Register) -> u8;
@@ -102,6 +131,20 @@ impl RegDataArray {
         RegDataArray(arr)
     }
 
+    /// Builds a `RegDataArray` in the documented post-boot-ROM register
+    /// state (AF=0x01B0, BC=0x0013, DE=0x00D8, HL=0x014D, SP=0xFFFE,
+    /// PC=0x0100), for frontends that want to skip running the boot ROM.
+    pub fn post_boot() -> RegDataArray {
+        let mut regs = RegDataArray::new([0; 12]);
+        regs.write_dword(Register::AF, 0x01B0);
+        regs.write_dword(Register::BC, 0x0013);
+        regs.write_dword(Register::DE, 0x00D8);
+        regs.write_dword(Register::HL, 0x014D);
+        regs.write_dword(Register::SP, 0xFFFE);
+        regs.write_dword(Register::PC, 0x0100);
+        regs
+    }
+
     /// Determines the starting index in a RegDataArray for the given register.
     fn get_idx_for_register(&self, reg: Register) -> usize {
         match reg {
@@ -119,6 +162,70 @@ impl RegDataArray {
     }
 }
 
+/// The wire representation used to (de)serialize a `RegDataArray`: logical
+/// 16-bit register values rather than the raw byte array. `RegDataArray`'s
+/// internal layout stores each pair in whichever order matches the host's
+/// endianness (see `read_dword`/`write_dword`), so serializing the raw
+/// `[u8; 12]` directly would produce a save file that only restores
+/// correctly on a host with the same endianness it was written on. Storing
+/// the logical values here instead makes saves portable across hosts.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RegSnapshot {
+    af: u16,
+    bc: u16,
+    de: u16,
+    hl: u16,
+    sp: u16,
+    pc: u16,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> From<&'a RegDataArray> for RegSnapshot {
+    fn from(regs: &'a RegDataArray) -> RegSnapshot {
+        RegSnapshot {
+            af: regs.read_dword(Register::AF),
+            bc: regs.read_dword(Register::BC),
+            de: regs.read_dword(Register::DE),
+            hl: regs.read_dword(Register::HL),
+            sp: regs.read_dword(Register::SP),
+            pc: regs.read_dword(Register::PC),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<RegSnapshot> for RegDataArray {
+    fn from(snapshot: RegSnapshot) -> RegDataArray {
+        let mut regs = RegDataArray::new([0; 12]);
+        regs.write_dword(Register::AF, snapshot.af);
+        regs.write_dword(Register::BC, snapshot.bc);
+        regs.write_dword(Register::DE, snapshot.de);
+        regs.write_dword(Register::HL, snapshot.hl);
+        regs.write_dword(Register::SP, snapshot.sp);
+        regs.write_dword(Register::PC, snapshot.pc);
+        regs
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for RegDataArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::serde::Serializer
+    {
+        RegSnapshot::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for RegDataArray {
+    fn deserialize<D>(deserializer: D) -> Result<RegDataArray, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        RegSnapshot::deserialize(deserializer).map(RegDataArray::from)
+    }
+}
+
 impl RegOps for RegDataArray {
     fn read_word(&self, reg: Register) -> u8 {
         if reg.get_reg_size() != 1 {